@@ -15,23 +15,112 @@
 
 extern crate sdl2;
 
-use regex::Regex;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::WindowCanvas;
+use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+use sdl2::ttf::Font;
+use sdl2::video::WindowContext;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fs;
 use std::process::Command;
-use std::time::Duration;
 use structopt::clap::AppSettings;
 use structopt::StructOpt;
 
+// Used when --font is left unset
+const DEFAULT_FONT_PATH: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+// Overrides the built-in `Options` defaults when no matching flag was passed on
+// the command line. Read from `<config dir>/dbar/config.toml`
+#[derive(Deserialize, Default)]
+struct FileOptions {
+    command: Option<String>,
+    command_on_click: Option<String>,
+    floating: Option<bool>,
+    width: Option<u32>,
+    height: Option<u32>,
+    bg_col: Option<String>,
+    fg_col: Option<String>,
+    no_mouse_capture: Option<bool>,
+    initial_percent: Option<f32>,
+    title: Option<String>,
+    show_value: Option<bool>,
+    refresh_rate: Option<u64>,
+    font: Option<String>,
+    font_size: Option<u16>,
+    step: Option<f32>,
+    opacity: Option<f32>,
+    transparent: Option<bool>,
+    orientation: Option<String>,
+    always_on_top: Option<bool>,
+    #[serde(default)]
+    keys: HashMap<String, String>,
+}
+
+// Remappable actions consulted from the [keys] config section
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    Confirm,
+    Cancel,
+    StepUp,
+    StepDown,
+    RunCommand,
+}
+
+impl Action {
+    fn from_str(s: &str) -> Option<Action> {
+        match s {
+            "confirm" => Some(Action::Confirm),
+            "cancel" => Some(Action::Cancel),
+            "step-up" => Some(Action::StepUp),
+            "step-down" => Some(Action::StepDown),
+            "run-command" => Some(Action::RunCommand),
+            _ => None,
+        }
+    }
+}
+
+fn default_keymap() -> HashMap<Keycode, Action> {
+    let mut keymap = HashMap::new();
+    keymap.insert(Keycode::Return, Action::Confirm);
+    keymap.insert(Keycode::Escape, Action::Cancel);
+    keymap.insert(Keycode::Right, Action::StepUp);
+    keymap.insert(Keycode::Up, Action::StepUp);
+    keymap.insert(Keycode::Left, Action::StepDown);
+    keymap.insert(Keycode::Down, Action::StepDown);
+    keymap
+}
+
+// Read & parse the config file, if one exists. Absent or unparseable config
+// is treated the same as an empty one so dbar still runs with just built-in
+// defaults & command-line flags
+fn load_config() -> FileOptions {
+    let path = dirs::config_dir().map(|dir| dir.join("dbar").join("config.toml"));
+    path.and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+// Apply a config-file default to `$opt.$field` unless the matching flag was
+// explicitly given on the command line. structopt names each arg after its
+// field in kebab-case, hence the '_' -> '-' mapping
+macro_rules! apply_default {
+    ($opt:expr, $matches:expr, $field:ident, $value:expr) => {
+        if $matches.occurrences_of(stringify!($field).replace('_', "-")) == 0 {
+            if let Some(v) = $value {
+                $opt.$field = v;
+            }
+        }
+    };
+}
+
 // Command line options
 #[derive(StructOpt)]
 #[structopt(
-    about = "A simple slider bar. Left click to select a value in the (inclusive) range from <start> to <end>. Continuously execute a command with --command. Return to print the current value and exit regardless of options. ESC to cancel & return nothing.",
+    about = "A simple slider bar. Left click to select a value in the (inclusive) range from <start> to <end>. Left/Right/Up/Down arrows nudge the value by --step, Home/End jump to <start>/<end>, and typing digits/./- then Return enters an exact value. Continuously execute a command with --command. Return (outside of typed entry) prints the current value and exits regardless of options. ESC to cancel & return nothing.",
     setting = AppSettings::AllowNegativeNumbers)]
 struct Options {
     #[structopt(default_value = "0")]
@@ -56,10 +145,10 @@ struct Options {
     #[structopt(short = "y", long, default_value = "50", help = "Height of the window")]
     height: u32,
 
-    #[structopt(long, default_value = "#222244", help = "The background colour in #rrggbb hex format")]
+    #[structopt(long, default_value = "#222244", help = "The background colour: #rgb, #rrggbb, #rrggbbaa, the 0x-prefixed equivalents, or a named colour")]
     bg_col: String,
 
-    #[structopt(long, default_value = "#9c99c3", help = "The bar colour in #rrggbb hex format")]
+    #[structopt(long, default_value = "#9c99c3", help = "The bar colour: #rgb, #rrggbb, #rrggbbaa, the 0x-prefixed equivalents, or a named colour")]
     fg_col: String,
 
     #[structopt(long, help = "Do not capture/grab the mouse cursor")]
@@ -76,17 +165,97 @@ struct Options {
 
     #[structopt(short = "r", long, default_value = "15", help = "Milliseconds in between bar redraws - lower is smoother but more compute intensive")]
     refresh_rate: u64,
+
+    #[structopt(long, default_value = "", help = "Path to a TTF/OTF font used to draw the current value over the bar. Falls back to /usr/share/fonts/truetype/dejavu/DejaVuSans.ttf if unset")]
+    font: String,
+
+    #[structopt(long, default_value = "16", help = "Point size of --font")]
+    font_size: u16,
+
+    #[structopt(long, default_value = "1", help = "Value-unit amount the arrow keys nudge the bar by")]
+    step: f32,
+
+    #[structopt(long, default_value = "1.0", help = "Window opacity, from 0.0 (invisible) to 1.0 (opaque)")]
+    opacity: f32,
+
+    #[structopt(long, help = "Clear to a fully transparent background instead of --bg-col, so only the filled bar is visible. Requires a compositor")]
+    transparent: bool,
+
+    #[structopt(long, default_value = "horizontal", help = "Fill direction: \"horizontal\" fills left-to-right using <width>, \"vertical\" fills bottom-to-top using <height>")]
+    orientation: Orientation,
+
+    #[structopt(long, help = "Keep the window above all others")]
+    always_on_top: bool,
+}
+
+// Which axis & direction the bar fills along
+#[derive(Clone, Copy, PartialEq)]
+enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+impl std::str::FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Orientation, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "horizontal" => Ok(Orientation::Horizontal),
+            "vertical" => Ok(Orientation::Vertical),
+            _ => Err(format!("Invalid orientation: '{}' (expected \"horizontal\" or \"vertical\")", s)),
+        }
+    }
 }
 
 pub fn main() -> Result<(), String> {
-    let opt = Options::from_args(); // Parse command line options
+    let matches = Options::clap().get_matches();
+    let mut opt = Options::from_clap(&matches); // Parse command line options
+
+    // Layer the config file in as defaults for whichever flags weren't
+    // explicitly passed, then let the command line take precedence
+    let file_opt = load_config();
+    apply_default!(opt, matches, command, file_opt.command);
+    apply_default!(opt, matches, command_on_click, file_opt.command_on_click);
+    apply_default!(opt, matches, floating, file_opt.floating);
+    apply_default!(opt, matches, width, file_opt.width);
+    apply_default!(opt, matches, height, file_opt.height);
+    apply_default!(opt, matches, bg_col, file_opt.bg_col);
+    apply_default!(opt, matches, fg_col, file_opt.fg_col);
+    apply_default!(opt, matches, no_mouse_capture, file_opt.no_mouse_capture);
+    apply_default!(opt, matches, initial_percent, file_opt.initial_percent);
+    apply_default!(opt, matches, title, file_opt.title);
+    apply_default!(opt, matches, show_value, file_opt.show_value);
+    apply_default!(opt, matches, refresh_rate, file_opt.refresh_rate);
+    apply_default!(opt, matches, font, file_opt.font);
+    apply_default!(opt, matches, font_size, file_opt.font_size);
+    apply_default!(opt, matches, step, file_opt.step);
+    apply_default!(opt, matches, opacity, file_opt.opacity);
+    apply_default!(opt, matches, transparent, file_opt.transparent);
+    if matches.occurrences_of("orientation") == 0 {
+        if let Some(o) = file_opt.orientation.as_deref().and_then(|s| s.parse::<Orientation>().ok()) {
+            opt.orientation = o;
+        }
+    }
+    apply_default!(opt, matches, always_on_top, file_opt.always_on_top);
+
+    // [keys] section remaps actions onto arbitrary Keycodes over the defaults
+    let mut keymap = default_keymap();
+    for (key_name, action_name) in &file_opt.keys {
+        if let (Some(code), Some(action)) =
+            (Keycode::from_name(key_name), Action::from_str(action_name))
+        {
+            keymap.retain(|_, a| *a != action);
+            keymap.insert(code, action);
+        }
+    }
+
     // Sanitize inputs
     assert!(opt.start < opt.end,
             "<start> = {} must be smaller than <end> = {}", opt.start, opt.end);
     assert!(opt.width > 1 || opt.height > 1,
             "<width> = {} and <height> = {} must be greater than 0", opt.width, opt.height);
-    let bg_col = string_to_color(&opt.bg_col[..]);
-    let fg_col = string_to_color(&opt.fg_col[..]);
+    let bg_col = string_to_color(&opt.bg_col[..])?;
+    let fg_col = string_to_color(&opt.fg_col[..])?;
 
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
@@ -101,15 +270,43 @@ pub fn main() -> Result<(), String> {
         .present_vsync()
         .build().unwrap();
 
+    canvas.window_mut().set_opacity(opt.opacity).map_err(|e| e.to_string())?;
+    canvas.window_mut().set_always_on_top(opt.always_on_top);
+
+    // In --transparent mode the backbuffer is cleared to Color::RGBA(0, 0, 0, 0);
+    // canvas.clear() writes that colour as is, whatever the blend mode. Blend
+    // mode makes the fg_col fill (e.g. a #rrggbbaa colour) alpha-blend over that
+    // transparent background instead of overwriting it. Genuine desktop-level
+    // transparency additionally requires a compositor that honours per-pixel
+    // window alpha; SDL2 has no portable "create a transparent window" flag, so
+    // on non-compositing setups --transparent will still show as opaque
+    if opt.transparent {
+        canvas.set_blend_mode(BlendMode::Blend);
+    }
+
     // Conditionally grab window/capture mouse
     if !opt.no_mouse_capture {
         sdl_context.mouse().set_relative_mouse_mode(true);
     }
 
+    // Set up the font renderer used to draw the live value over the bar
+    let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string())?;
+    let font_path = if opt.font.is_empty() { DEFAULT_FONT_PATH } else { &opt.font[..] };
+    let font = ttf_context.load_font(font_path, opt.font_size)
+        .map_err(|e| format!("failed to load font '{}': {}", font_path, e))?;
+    let texture_creator = canvas.texture_creator();
+    let mut glyphs = GlyphCache::new(font);
+
+    // The dimension the bar fills along: --width when horizontal, --height when vertical
+    let extent = match opt.orientation {
+        Orientation::Horizontal => opt.width,
+        Orientation::Vertical => opt.height,
+    };
+
     // Lazily evaluate the bar value for potential reuse
     let mut dbar_value = LazyResult::new(|x: i32| {
         let range = (opt.end - opt.start).abs();
-        let result = opt.start + range * (x as f32 / opt.width as f32);
+        let result = opt.start + range * (x as f32 / extent as f32);
         if opt.floating { result }
         else { result.round() }
     });
@@ -120,16 +317,32 @@ pub fn main() -> Result<(), String> {
     let mut last_val: Option<f32> = None;
     let mut fill_pixels = 0;
     let mut first_draw = true;
-
-    // Main execution loop
+    let pixels_per_unit = extent as f32 / (opt.end - opt.start).abs();
+    let step_pixels = (opt.step * pixels_per_unit).round() as i32;
+    let mut input_buffer: Option<String> = None;
+
+    // --command re-runs on every value change and --command-on-click on a click
+    // or the run-command key; the loop below only wakes on a timer when one of
+    // them is configured
+    let have_command_on_click = !opt.command_on_click.is_empty();
+
+    // Main execution loop. Block on the event queue instead of spinning so the
+    // bar sits at zero CPU while idle. Only wake on the refresh_rate cadence
+    // when a command is configured (so its output can't go stale) or the first
+    // draw is still pending (so the bar is guaranteed to paint at least once
+    // rather than waiting on some external event to arrive); otherwise block
+    // indefinitely until real input arrives
     'running: loop {
-        for event in events.poll_iter() {
+        let mut keyboard_moved = false;
+
+        let woken_event = if first_draw || have_command || have_command_on_click {
+            events.wait_event_timeout(opt.refresh_rate as u32)
+        } else {
+            Some(events.wait_event())
+        };
+        for event in woken_event.into_iter().chain(events.poll_iter()) {
             match event {
-                Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                }
-                | Event::Quit { .. } => break 'running,
+                Event::Quit { .. } => break 'running,
                 Event::MouseButtonDown {
                     mouse_btn: MouseButton::Left,
                     ..
@@ -141,53 +354,128 @@ pub fn main() -> Result<(), String> {
                         break 'running
                     }
                 },
+                // Confirm/cancel/step-up/step-down/run-command are remappable
+                // via the [keys] config section; anything else falls through
+                // to the fixed Home/End/Backspace/type-mode handling below
+                Event::KeyDown { keycode: Some(code), .. } if keymap.contains_key(&code) => {
+                    match keymap[&code] {
+                        // Typed entry takes precedence: apply & clear the buffer
+                        // instead of printing & exiting. A buffer that doesn't
+                        // parse as a number is just cleared
+                        Action::Confirm => {
+                            if let Some(buf) = input_buffer.take() {
+                                if let Ok(typed) = buf.parse::<f32>() {
+                                    fill_pixels = clamp_pixels(((typed - opt.start) * pixels_per_unit).round() as i32, extent);
+                                    keyboard_moved = true;
+                                }
+                            } else {
+                                println!("{}", dbar_value.value(fill_pixels));
+                                break 'running
+                            }
+                        }
+                        Action::Cancel => break 'running,
+                        Action::StepUp => {
+                            fill_pixels = clamp_pixels(fill_pixels + step_pixels, extent);
+                            keyboard_moved = true;
+                        }
+                        Action::StepDown => {
+                            fill_pixels = clamp_pixels(fill_pixels - step_pixels, extent);
+                            keyboard_moved = true;
+                        }
+                        Action::RunCommand => {
+                            if !opt.command_on_click.is_empty() {
+                                run_command(&opt.command_on_click, dbar_value.value(fill_pixels), on_windows);
+                            }
+                        }
+                    }
+                }
                 Event::KeyDown {
-                    keycode: Some(Keycode::Return),
-                    ..
+                    keycode: Some(Keycode::Home), ..
+                } => {
+                    fill_pixels = 0;
+                    keyboard_moved = true;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::End), ..
+                } => {
+                    fill_pixels = extent as i32;
+                    keyboard_moved = true;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backspace), ..
                 } => {
-                    println!("{}", dbar_value.value(fill_pixels));
-                    break 'running
+                    if let Some(buf) = input_buffer.as_mut() { buf.pop(); }
+                }
+                // Any other digit/./- key accumulates into the type-mode input buffer
+                Event::KeyDown {
+                    keycode: Some(code), ..
+                } if entry_char(code).is_some() => {
+                    input_buffer.get_or_insert_with(String::new).push(entry_char(code).unwrap());
                 }
                 _ => {},
             }
         }
 
-        // If the mouse moved or this is the first iteration
-        let mouse_movement = events.relative_mouse_state().x();
-        if (mouse_movement != 0) | first_draw {
+        // If the mouse moved, a key nudged the value, or this is the first iteration.
+        // Vertical mode drives fill_pixels from the y axis, inverted so moving
+        // the mouse up (screen y decreasing) fills the bar (bottom-up)
+        let mouse_movement = match opt.orientation {
+            Orientation::Horizontal => events.relative_mouse_state().x(),
+            Orientation::Vertical => -events.relative_mouse_state().y(),
+        };
+        if (mouse_movement != 0) | first_draw | keyboard_moved {
 
             if first_draw { // On 1st iteration, compute bar fill from initial %
                 first_draw = false;
-                fill_pixels = (opt.initial_percent * opt.width as f32) as i32;
-            } else if opt.no_mouse_capture {
-                fill_pixels = events.mouse_state().x();
-            } else {        // otherwise compute using mouse movement
-                fill_pixels += mouse_movement;
-                fill_pixels = if fill_pixels > opt.width as i32 { opt.width as i32 }
-                              else if fill_pixels < 0 { 0 }
-                              else { fill_pixels }
-            }
-
-            // Render the bar
-            canvas.set_draw_color(bg_col);
+                fill_pixels = (opt.initial_percent * extent as f32) as i32;
+            } else if opt.no_mouse_capture && mouse_movement != 0 {
+                fill_pixels = match opt.orientation {
+                    Orientation::Horizontal => events.mouse_state().x(),
+                    Orientation::Vertical => opt.height as i32 - events.mouse_state().y(),
+                };
+            } else if !opt.no_mouse_capture && mouse_movement != 0 {
+                fill_pixels = clamp_pixels(fill_pixels + mouse_movement, extent);
+            } // else fill_pixels was already set by the keyboard handling above
+
+            // Check if the current value is different from last value
+            let value_changed = if let Some(v) = last_val {
+                v != dbar_value.value(fill_pixels)
+            } else { true };
+
+            // Update last value for next time
+            if value_changed { last_val = Some(dbar_value.value(fill_pixels)); }
+
+            // Render the bar. In --transparent mode, clear to fully transparent
+            // instead of bg_col so only the filled region is visible over the desktop
+            canvas.set_draw_color(if opt.transparent { Color::RGBA(0, 0, 0, 0) } else { bg_col });
             canvas.clear();
             canvas.set_draw_color(fg_col);
-            canvas.fill_rect(Rect::new(0, 0, (fill_pixels) as u32, opt.height))
-                  .expect("failed to draw rectangle");
-            canvas.present();
-
-            // Only compute last value in the cases it's used (-c || -v).
-            let value_changed = if have_command || opt.show_value {
-                // Check if the current value is different from last value
-                let changed = if let Some(v) = last_val {
-                    v != dbar_value.value(fill_pixels)
-                } else { true };
+            let fill_rect = match opt.orientation {
+                Orientation::Horizontal => Rect::new(0, 0, fill_pixels as u32, opt.height),
+                Orientation::Vertical => Rect::new(0, opt.height as i32 - fill_pixels, opt.width, fill_pixels as u32),
+            };
+            canvas.fill_rect(fill_rect).expect("failed to draw rectangle");
+
+            // Draw the live value as text on every redraw, not just when it changes,
+            // otherwise the number flickers out on frames where the bar moved but
+            // the rounded value didn't. GlyphCache only rasterizes/uploads when the
+            // string itself is new, so re-blitting the cached texture here is cheap
+            let text = dbar_value.value(fill_pixels).to_string();
+            let texture = glyphs.texture(&texture_creator, &text, bg_col)?;
+            let query = texture.query();
+            // Center on the fill midpoint, not the window: the fill midpoint lies
+            // in the fg_col region, so the middle of the bg_col text contrasts.
+            // Text wider than the fill still overhangs onto the bg_col background.
+            // Centering on the window instead would put it over the bg_col
+            // background below 50% fill, where bg_col-on-bg_col text is invisible
+            let text_center = match opt.orientation {
+                Orientation::Horizontal => (fill_pixels / 2, opt.height as i32 / 2),
+                Orientation::Vertical => (opt.width as i32 / 2, opt.height as i32 - fill_pixels / 2),
+            };
+            let dst = Rect::from_center(text_center, query.width, query.height);
+            canvas.copy(texture, None, dst)?;
 
-                // Update last value for next time
-                if changed { last_val = Some(dbar_value.value(fill_pixels)); }
-                changed
-
-            } else { false }; // value_changed is unused ∴ return arbitrary bool
+            canvas.present();
 
             // Write value to window title if requested & the value has changed
             if opt.show_value && value_changed {
@@ -201,8 +489,6 @@ pub fn main() -> Result<(), String> {
                 run_command(&opt.command, dbar_value.value(fill_pixels), on_windows);
             }
         }
-
-        std::thread::sleep(Duration::from_millis(opt.refresh_rate));
     }
 
     Ok(())
@@ -241,18 +527,114 @@ where
     }
 }
 
-fn string_to_color(hex_code: &str) -> Color {
-    // Check whether the string is a valid hex colour code
-    let re = Regex::new(r"^#[a-f,A-F,0-9]{6}").unwrap();
-    if ! re.is_match(hex_code) {
-        panic!("Invalid hex colour code: {}", hex_code);
+// Caches rasterized glyph textures keyed by string so we don't re-rasterize
+// & re-upload the same text every frame
+struct GlyphCache<'f> {
+    font: Font<'f, 'static>,
+    textures: HashMap<String, Texture<'f>>,
+}
+
+impl<'f> GlyphCache<'f> {
+    fn new(font: Font<'f, 'static>) -> GlyphCache<'f> {
+        GlyphCache {
+            font,
+            textures: HashMap::new(),
+        }
+    }
+
+    // Only rasterize & upload the text if we haven't rendered this exact string before
+    fn texture(
+        &mut self,
+        texture_creator: &'f TextureCreator<WindowContext>,
+        text: &str,
+        color: Color,
+    ) -> Result<&Texture<'f>, String> {
+        if !self.textures.contains_key(text) {
+            let surface = self.font.render(text)
+                .blended(color)
+                .map_err(|e| e.to_string())?;
+            let texture = texture_creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.textures.insert(text.to_string(), texture);
+        }
+        Ok(self.textures.get(text).unwrap())
+    }
+}
+
+// Clamp a pixel offset to the [0, extent] range (width, or height when vertical), as the bar can't fill beyond its own window
+fn clamp_pixels(x: i32, extent: u32) -> i32 {
+    if x > extent as i32 { extent as i32 }
+    else if x < 0 { 0 }
+    else { x }
+}
+
+// Maps the keys usable in type-mode entry to the character they accumulate
+fn entry_char(code: Keycode) -> Option<char> {
+    match code {
+        Keycode::Num0 | Keycode::Kp0 => Some('0'),
+        Keycode::Num1 | Keycode::Kp1 => Some('1'),
+        Keycode::Num2 | Keycode::Kp2 => Some('2'),
+        Keycode::Num3 | Keycode::Kp3 => Some('3'),
+        Keycode::Num4 | Keycode::Kp4 => Some('4'),
+        Keycode::Num5 | Keycode::Kp5 => Some('5'),
+        Keycode::Num6 | Keycode::Kp6 => Some('6'),
+        Keycode::Num7 | Keycode::Kp7 => Some('7'),
+        Keycode::Num8 | Keycode::Kp8 => Some('8'),
+        Keycode::Num9 | Keycode::Kp9 => Some('9'),
+        Keycode::Period | Keycode::KpPeriod => Some('.'),
+        Keycode::Minus | Keycode::KpMinus => Some('-'),
+        _ => None,
+    }
+}
+
+// Parses #rgb, #rrggbb, #rrggbbaa, the 0x-prefixed equivalents, and a small
+// set of named colours
+fn string_to_color(spec: &str) -> Result<Color, String> {
+    if let Some(c) = named_color(spec) {
+        return Ok(c);
     }
 
-    let r: u8 = u8::from_str_radix(&hex_code[1..3], 16).unwrap();
-    let g: u8 = u8::from_str_radix(&hex_code[3..5], 16).unwrap();
-    let b: u8 = u8::from_str_radix(&hex_code[5..7], 16).unwrap();
+    let hex = spec.strip_prefix('#')
+        .or_else(|| spec.strip_prefix("0x"))
+        .or_else(|| spec.strip_prefix("0X"))
+        .ok_or_else(|| format!("Invalid colour: {}", spec))?;
 
-    Color::RGB(r, g, b)
+    // Expand the 3-digit shorthand, e.g. "9c3" -> "99cc33"
+    let hex = if hex.len() == 3 {
+        hex.chars().flat_map(|c| std::iter::repeat(c).take(2)).collect()
+    } else {
+        hex.to_string()
+    };
+
+    let channel = |s: &str| {
+        u8::from_str_radix(s, 16).map_err(|_| format!("Invalid colour: {}", spec))
+    };
+
+    match hex.len() {
+        6 => Ok(Color::RGB(channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        8 => Ok(Color::RGBA(
+            channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?, channel(&hex[6..8])?,
+        )),
+        _ => Err(format!("Invalid colour: {}", spec)),
+    }
+}
+
+fn named_color(name: &str) -> Option<Color> {
+    match name.to_ascii_lowercase().as_str() {
+        "black" => Some(Color::RGB(0, 0, 0)),
+        "white" => Some(Color::RGB(255, 255, 255)),
+        "red" => Some(Color::RGB(255, 0, 0)),
+        "green" => Some(Color::RGB(0, 255, 0)),
+        "blue" => Some(Color::RGB(0, 0, 255)),
+        "yellow" => Some(Color::RGB(255, 255, 0)),
+        "cyan" => Some(Color::RGB(0, 255, 255)),
+        "magenta" => Some(Color::RGB(255, 0, 255)),
+        "gray" | "grey" => Some(Color::RGB(128, 128, 128)),
+        "orange" => Some(Color::RGB(255, 165, 0)),
+        "purple" => Some(Color::RGB(128, 0, 128)),
+        _ => None,
+    }
 }
 
 fn run_command(command: &String, value: f32, on_windows: bool) {